@@ -1,8 +1,9 @@
 #![warn(clippy::pedantic)]
-use ipnet::Ipv4Net;
+use ipnet::IpNet;
 use std::{
     fmt, fs,
-    net::{self, Ipv4Addr},
+    net::{self, IpAddr, SocketAddr, ToSocketAddrs},
+    str::FromStr,
 };
 
 // NOTE: These data structures only represent structure of a
@@ -18,9 +19,22 @@ use std::{
 pub enum ParserError {
     Ipnet(ipnet::AddrParseError),
     Net(net::AddrParseError),
-    MissingToken(String),
     Other(String),
-    BadFormat,
+
+    /// A token at `line`/`col` (both 1-indexed) didn't look like `expected`.
+    Expected {
+        line: usize,
+        col: usize,
+        expected: String,
+    },
+
+    /// A hostname endpoint failed to resolve via DNS.
+    Resolve(String),
+
+    /// A directive at `line` (1-indexed) failed a cross-directive check
+    /// (e.g. a `neighbor` naming an interface that doesn't exist) that can
+    /// only be evaluated once the whole config has been staged.
+    CrossRef { line: usize, message: String },
 
     InvalidLine(String, Box<ParserError>),
 }
@@ -30,9 +44,14 @@ impl fmt::Display for ParserError {
         match self {
             ParserError::Ipnet(e) => write!(f, "IPNet error: {e}"),
             ParserError::Net(e) => write!(f, "Net error: {e}"),
-            ParserError::MissingToken(token) => write!(f, "Missing token: {token}"),
             ParserError::Other(e) => write!(f, "Error: {e}"),
-            ParserError::BadFormat => write!(f, "Bad format"),
+            ParserError::Expected {
+                line,
+                col,
+                expected,
+            } => write!(f, "line {line}, column {col}: expected {expected}"),
+            ParserError::Resolve(e) => write!(f, "Failed to resolve hostname: {e}"),
+            ParserError::CrossRef { line, message } => write!(f, "line {line}: {message}"),
             ParserError::InvalidLine(line, e) => write!(f, "Invalid line: {line}\n{e}"),
         }
     }
@@ -50,15 +69,257 @@ impl From<net::AddrParseError> for ParserError {
     }
 }
 
-fn str_to_udp(input: &str) -> (Ipv4Addr, u16) {
-    let tokens = input.split(':').collect::<Vec<&str>>();
-    (
-        tokens[0].parse::<Ipv4Addr>().unwrap(),
-        tokens[1].parse::<u16>().unwrap(),
+/// Check that two addresses belong to the same IP family (both v4 or both v6)
+fn same_family(a: IpAddr, b: IpAddr) -> bool {
+    matches!(
+        (a, b),
+        (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_))
     )
 }
 
-#[derive(Debug, PartialEq)]
+/// A UDP endpoint as written in a config line: either a literal address, or
+/// an unresolved `hostname:port` pair whose hostname has been validated
+/// against RFC 1035 label syntax but not yet looked up.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UdpEndpoint {
+    Addr(SocketAddr),
+    Hostname(String, u16),
+}
+
+impl fmt::Display for UdpEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UdpEndpoint::Addr(addr) => write!(f, "{addr}"),
+            UdpEndpoint::Hostname(host, port) => write!(f, "{host}:{port}"),
+        }
+    }
+}
+
+impl FromStr for UdpEndpoint {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, ParserError> {
+        if let Ok(addr) = s.parse::<SocketAddr>() {
+            return Ok(UdpEndpoint::Addr(addr));
+        }
+
+        let (host, port) = s
+            .rsplit_once(':')
+            .ok_or_else(|| ParserError::Other(format!("Invalid UDP endpoint: {s}")))?;
+        let port: u16 = port
+            .parse()
+            .map_err(|_| ParserError::Other(format!("Invalid port in UDP endpoint: {s}")))?;
+        validate_hostname(host)?;
+
+        Ok(UdpEndpoint::Hostname(String::from(host), port))
+    }
+}
+
+/// Validate a hostname against RFC 1035 label syntax: labels separated by
+/// `.`, each 1-63 characters of letters/digits/hyphen (underscores
+/// tolerated), not starting or ending with a hyphen, 253 characters total.
+fn validate_hostname(host: &str) -> Result<(), ParserError> {
+    if host.is_empty() || host.len() > 253 {
+        return Err(ParserError::Other(format!(
+            "Invalid hostname (bad length): {host}"
+        )));
+    }
+
+    for label in host.split('.') {
+        let valid_chars = !label.is_empty()
+            && label.len() <= 63
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        let valid_hyphens = !label.starts_with('-') && !label.ends_with('-');
+
+        if !valid_chars || !valid_hyphens {
+            return Err(ParserError::Other(format!(
+                "Invalid hostname label {label:?} in {host}"
+            )));
+        }
+    }
+
+    // RFC 1123 3.2.1.1: the rightmost label must not be all-numeric, so a
+    // dotted-decimal string that failed to parse as an IP address (e.g. a
+    // typo'd/out-of-range literal like `999.999.999.999`) is rejected here
+    // rather than silently accepted as a hostname.
+    if host
+        .rsplit('.')
+        .next()
+        .is_some_and(|tld| tld.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(ParserError::Other(format!(
+            "Invalid hostname (looks like a malformed IP literal): {host}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Resolve any hostname endpoint to a concrete address via `ToSocketAddrs`.
+fn resolve_endpoint(endpoint: &UdpEndpoint) -> Result<UdpEndpoint, ParserError> {
+    match endpoint {
+        UdpEndpoint::Addr(addr) => Ok(UdpEndpoint::Addr(*addr)),
+        UdpEndpoint::Hostname(host, port) => (host.as_str(), *port)
+            .to_socket_addrs()
+            .map_err(|e| ParserError::Resolve(format!("{host}:{port}: {e}")))?
+            .next()
+            .map(UdpEndpoint::Addr)
+            .ok_or_else(|| ParserError::Resolve(format!("{host}:{port}: no addresses found"))),
+    }
+}
+
+/// A backtracking cursor over a single config line, modeled on the standard
+/// library's internal `net::parser`. Primitives advance `remaining` on
+/// success; `read_atomically` rewinds the cursor on failure so a caller can
+/// try an alternative without leaving it in a partially-consumed state.
+struct Parser<'a> {
+    line_no: usize,
+    full_line: &'a str,
+    remaining: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(line_no: usize, line: &'a str) -> Self {
+        Self {
+            line_no,
+            full_line: line,
+            remaining: line,
+        }
+    }
+
+    /// 1-indexed byte column of the unconsumed remainder within the line.
+    ///
+    /// Computed from the byte offset of `remaining`'s start within
+    /// `full_line` rather than a length difference: `strip_comment` shrinks
+    /// `remaining` by truncating its *tail*, not its front, so subtracting
+    /// lengths would wrongly inflate the column by the comment's length.
+    fn col(&self) -> usize {
+        (self.remaining.as_ptr() as usize - self.full_line.as_ptr() as usize) + 1
+    }
+
+    fn expected(&self, what: &str) -> ParserError {
+        ParserError::Expected {
+            line: self.line_no,
+            col: self.col(),
+            expected: String::from(what),
+        }
+    }
+
+    /// Run `f`; if it fails, restore the cursor to where it was before the call.
+    fn read_atomically<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, ParserError>,
+    ) -> Result<T, ParserError> {
+        let snapshot = self.remaining;
+        let result = f(self);
+        if result.is_err() {
+            self.remaining = snapshot;
+        }
+        result
+    }
+
+    fn skip_ws(&mut self) {
+        self.remaining = self.remaining.trim_start_matches(char::is_whitespace);
+    }
+
+    fn strip_comment(&mut self) {
+        if let Some(idx) = self.remaining.find('#') {
+            self.remaining = &self.remaining[..idx];
+        }
+    }
+
+    /// Whether nothing other than whitespace/a comment remains on the line.
+    fn is_empty(&mut self) -> bool {
+        self.strip_comment();
+        self.skip_ws();
+        self.remaining.is_empty()
+    }
+
+    /// Read the next whitespace-delimited token, advancing past it.
+    fn read_token(&mut self) -> Result<&'a str, ParserError> {
+        self.read_atomically(|p| {
+            p.skip_ws();
+            if p.remaining.is_empty() || p.remaining.starts_with('#') {
+                return Err(p.expected("a token"));
+            }
+            let end = p
+                .remaining
+                .find(char::is_whitespace)
+                .unwrap_or(p.remaining.len());
+            let (tok, rest) = p.remaining.split_at(end);
+            p.remaining = rest;
+            Ok(tok)
+        })
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParserError> {
+        self.read_atomically(|p| {
+            p.skip_ws();
+            let start_col = p.col();
+            if p.read_token()? == keyword {
+                Ok(())
+            } else {
+                Err(ParserError::Expected {
+                    line: p.line_no,
+                    col: start_col,
+                    expected: format!("keyword `{keyword}`"),
+                })
+            }
+        })
+    }
+
+    fn read_ipnet(&mut self) -> Result<IpNet, ParserError> {
+        self.read_atomically(|p| {
+            p.skip_ws();
+            let start_col = p.col();
+            let tok = p.read_token()?;
+            tok.parse().map_err(|_| ParserError::Expected {
+                line: p.line_no,
+                col: start_col,
+                expected: String::from("an IP network (address/prefix)"),
+            })
+        })
+    }
+
+    fn read_ip(&mut self) -> Result<IpAddr, ParserError> {
+        self.read_atomically(|p| {
+            p.skip_ws();
+            let start_col = p.col();
+            let tok = p.read_token()?;
+            tok.parse().map_err(|_| ParserError::Expected {
+                line: p.line_no,
+                col: start_col,
+                expected: String::from("an IP address"),
+            })
+        })
+    }
+
+    /// Read a `addr:port` (`[addr]:port` for IPv6) or `hostname:port` UDP endpoint.
+    fn read_udp_endpoint(&mut self) -> Result<UdpEndpoint, ParserError> {
+        self.read_atomically(|p| {
+            p.skip_ws();
+            let start_col = p.col();
+            let tok = p.read_token()?;
+            tok.parse().map_err(|_: ParserError| ParserError::Expected {
+                line: p.line_no,
+                col: start_col,
+                expected: String::from("a UDP endpoint (`addr:port` or `hostname:port`)"),
+            })
+        })
+    }
+
+    /// Collect whatever whitespace-delimited tokens remain (after the
+    /// directive), for directives that aren't recognized and so can't be
+    /// parsed into a typed form.
+    fn remaining_tokens(&mut self) -> Vec<&'a str> {
+        self.strip_comment();
+        self.remaining.split_whitespace().collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RoutingType {
     None,
     Static,
@@ -84,80 +345,90 @@ impl TryFrom<&str> for RoutingType {
     }
 }
 
+impl fmt::Display for RoutingType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mode = match self {
+            RoutingType::None => "none",
+            RoutingType::Static => "static",
+            RoutingType::Rip => "rip",
+        };
+        write!(f, "{mode}")
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct InterfaceConfig {
     pub name: String,
-    // Ip address of the interface + prefix
-    pub assigned_prefix: Ipv4Net,
-    pub assigned_ip: Ipv4Addr,
-    pub udp_addr: Ipv4Addr,
-    pub udp_port: u16,
+    // Ip address of the interface + prefix (v4 or v6)
+    pub assigned_prefix: IpNet,
+    pub assigned_ip: IpAddr,
+    pub udp_endpoint: UdpEndpoint,
 }
 
-impl TryFrom<Vec<&str>> for InterfaceConfig {
-    type Error = ParserError;
-
-    /// Create an `InterfaceConfig` from a vector of tokens
-    /// Format: interface <name> <virtual IP address>/<prefix> <UDP address>:<UDP port>
-    fn try_from(tokens: Vec<&str>) -> Result<Self, ParserError> {
-        if tokens.len() != 4 {
-            return Err(ParserError::BadFormat);
-        }
-
-        let name = String::from(tokens[1]);
-        let assigned_prefix: Ipv4Net = tokens[2].parse()?;
-        let (udp_addr, udp_port) = str_to_udp(tokens[3]);
+impl InterfaceConfig {
+    /// Parse `interface <name> <virtual IP address>/<prefix> <UDP address>:<UDP port>`
+    fn parse(p: &mut Parser) -> Result<Self, ParserError> {
+        let name = String::from(p.read_token()?);
+        let assigned_prefix = p.read_ipnet()?;
+        let udp_endpoint = p.read_udp_endpoint()?;
 
         Ok(Self {
             name,
             assigned_prefix,
             assigned_ip: assigned_prefix.addr(),
-            udp_addr,
-            udp_port,
+            udp_endpoint,
         })
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for InterfaceConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "interface {} {} {}",
+            self.name, self.assigned_prefix, self.udp_endpoint
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct NeighborConfig {
-    pub dest_addr: Ipv4Addr,
-    pub udp_addr: Ipv4Addr,
-    pub udp_port: u16,
+    pub dest_addr: IpAddr,
+    pub udp_endpoint: UdpEndpoint,
     pub interface_name: String,
 }
 
-impl TryFrom<Vec<&str>> for NeighborConfig {
-    type Error = ParserError;
-
-    /// Create a `NeighborConfig` from a vector of tokens
-    /// Format: neighbor <virtual IP> at <UDP address>:<UDP port> via <interface>
-    fn try_from(tokens: Vec<&str>) -> Result<Self, ParserError> {
-        if tokens.len() != 6 {
-            return Err(ParserError::BadFormat);
-        }
-
-        let dest_addr: Ipv4Addr = tokens[1].parse()?;
-        if tokens[2] != "at" {
-            return Err(ParserError::MissingToken(String::from("at")));
-        }
-        let (udp_addr, udp_port) = str_to_udp(tokens[3]);
-        if tokens[4] != "via" {
-            return Err(ParserError::MissingToken(String::from("via")));
-        }
+impl NeighborConfig {
+    /// Parse `neighbor <virtual IP> at <UDP address>:<UDP port> via <interface>`
+    fn parse(p: &mut Parser) -> Result<Self, ParserError> {
+        let dest_addr = p.read_ip()?;
+        p.expect_keyword("at")?;
+        let udp_endpoint = p.read_udp_endpoint()?;
+        p.expect_keyword("via")?;
+        let interface_name = String::from(p.read_token()?);
 
         Ok(Self {
             dest_addr,
-            udp_addr,
-            udp_port,
-            interface_name: String::from(tokens[5]),
+            udp_endpoint,
+            interface_name,
         })
     }
 }
 
-pub type StaticRoute = (Ipv4Net, Ipv4Addr);
+impl fmt::Display for NeighborConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "neighbor {} at {} via {}",
+            self.dest_addr, self.udp_endpoint, self.interface_name
+        )
+    }
+}
+
+pub type StaticRoute = (IpNet, IpAddr);
 
 /// `IPConfig` struct to hold all the parsed data
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 pub struct IPConfig {
     pub interfaces: Vec<InterfaceConfig>,
     pub neighbors: Vec<NeighborConfig>,
@@ -165,9 +436,71 @@ pub struct IPConfig {
     pub routing_mode: RoutingType,
 
     // ROUTERS only so making an option
-    pub rip_neighbors: Option<Vec<Ipv4Addr>>,
+    pub rip_neighbors: Option<Vec<IpAddr>>,
+
+    // Locally-originated prefixes advertised into RIP
+    pub rip_originate: Vec<IpNet>,
 
     pub static_routes: Vec<StaticRoute>, // prefix -> addr
+
+    /// Directives that weren't one of the known keywords, kept as
+    /// `(directive, args)` rather than discarded. Only populated when
+    /// parsing with [`IPConfig::parse`]; [`IPConfig::parse_strict`] errors
+    /// on these instead.
+    pub unrecognized: Vec<(String, Vec<String>)>,
+}
+
+/// Renders a canonical config file string: `interface`/`neighbor` lines first,
+/// then `routing`, then `route` lines, then `rip advertise-to`/`rip originate`
+/// lines, then any unrecognized directives verbatim. Parsing this output
+/// reproduces an equal `IPConfig`.
+impl fmt::Display for IPConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for iface in &self.interfaces {
+            writeln!(f, "{iface}")?;
+        }
+        for neighbor in &self.neighbors {
+            writeln!(f, "{neighbor}")?;
+        }
+        writeln!(f, "routing {}", self.routing_mode)?;
+        for (prefix, addr) in &self.static_routes {
+            writeln!(f, "route {prefix} via {addr}")?;
+        }
+        if let Some(rip_neighbors) = &self.rip_neighbors {
+            for addr in rip_neighbors {
+                writeln!(f, "rip advertise-to {addr}")?;
+            }
+        }
+        for prefix in &self.rip_originate {
+            writeln!(f, "rip originate {prefix}")?;
+        }
+        for (directive, args) in &self.unrecognized {
+            write!(f, "{directive}")?;
+            for arg in args {
+                write!(f, " {arg}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of the first parse pass: every line tokenized and dispatched
+/// by directive, but not yet cross-validated against the rest of the
+/// config. Interface, neighbor and `rip` entries keep the 1-indexed source
+/// line number of their directive so the second pass can attribute a
+/// cross-reference error (e.g. a `rip advertise-to` with no matching
+/// neighbor) to the line that caused it; static routes and unrecognized
+/// directives carry no line number.
+#[derive(Default)]
+struct Staged {
+    interfaces: Vec<(usize, InterfaceConfig)>,
+    neighbors: Vec<(usize, NeighborConfig)>,
+    routing_mode: RoutingType,
+    rip_advertise: Vec<(usize, IpAddr)>,
+    rip_originate: Vec<(usize, IpNet)>,
+    static_routes: Vec<StaticRoute>,
+    unrecognized: Vec<(String, Vec<String>)>,
 }
 
 impl IPConfig {
@@ -199,242 +532,697 @@ impl IPConfig {
         Ok(ip_config)
     }
 
-    /// Parse a config based on its contents as a string
+    /// Parse a config based on its contents as a string.
+    ///
+    /// Parsing happens in two passes: every line is tokenized first, so a
+    /// directive may reference another declared later in the file (e.g. a
+    /// `rip advertise-to` whose neighbor appears further down). Directives
+    /// that aren't recognized are collected into
+    /// [`unrecognized`](Self::unrecognized) rather than rejected; use
+    /// [`parse_strict`](Self::parse_strict) to reject them instead.
     ///
     /// # Errors
     /// Returns an error if there is an issue parsing the file
     pub fn parse(&mut self, config: &str) -> Result<(), ParserError> {
-        for line in config.lines() {
-            match self.parse_line(line) {
-                Ok(()) | Err(ParserError::InvalidLine(_, _)) => {}
-                Err(e) => return Err(ParserError::InvalidLine(String::from(line), Box::new(e))),
-            };
-        }
+        *self = Self::assemble(Self::stage(config, false)?)?;
+        Ok(())
+    }
+
+    /// Like [`parse`](Self::parse), but errors on the first unrecognized
+    /// directive instead of collecting it into
+    /// [`unrecognized`](Self::unrecognized).
+    ///
+    /// # Errors
+    /// Returns an error if there is an issue parsing the file, including an
+    /// unrecognized directive.
+    pub fn parse_strict(&mut self, config: &str) -> Result<(), ParserError> {
+        *self = Self::assemble(Self::stage(config, true)?)?;
         Ok(())
     }
 
-    /// Parse a single line of the config, updating the `IPConfig`
-    fn parse_line(&mut self, line: &str) -> Result<(), ParserError> {
-        let mut tokens = line.split_ascii_whitespace().collect::<Vec<&str>>();
+    /// Resolve every hostname UDP endpoint via DNS, producing a config where
+    /// all endpoints are concrete `SocketAddr`s.
+    ///
+    /// # Errors
+    /// Returns `ParserError::Resolve` if a hostname fails to resolve.
+    pub fn resolve(&self) -> Result<Self, ParserError> {
+        let interfaces = self
+            .interfaces
+            .iter()
+            .map(|iface| {
+                Ok(InterfaceConfig {
+                    name: iface.name.clone(),
+                    assigned_prefix: iface.assigned_prefix,
+                    assigned_ip: iface.assigned_ip,
+                    udp_endpoint: resolve_endpoint(&iface.udp_endpoint)?,
+                })
+            })
+            .collect::<Result<Vec<_>, ParserError>>()?;
 
-        // Remove # and all tokens after it
-        tokens.truncate(
-            tokens
-                .iter()
-                .position(|&x| x == "#")
-                .unwrap_or(tokens.len()),
-        );
+        let neighbors = self
+            .neighbors
+            .iter()
+            .map(|neighbor| {
+                Ok(NeighborConfig {
+                    dest_addr: neighbor.dest_addr,
+                    udp_endpoint: resolve_endpoint(&neighbor.udp_endpoint)?,
+                    interface_name: neighbor.interface_name.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, ParserError>>()?;
+
+        Ok(Self {
+            interfaces,
+            neighbors,
+            routing_mode: self.routing_mode,
+            rip_neighbors: self.rip_neighbors.clone(),
+            rip_originate: self.rip_originate.clone(),
+            static_routes: self.static_routes.clone(),
+            unrecognized: self.unrecognized.clone(),
+        })
+    }
 
-        if tokens.is_empty() {
-            return Ok(());
+    /// Build an error attributing a cross-directive validation failure to
+    /// the source line of the directive that triggered it.
+    fn line_error(line_no: usize, message: &str) -> ParserError {
+        ParserError::CrossRef {
+            line: line_no,
+            message: String::from(message),
         }
+    }
+
+    /// First pass: tokenize every line into a `Staged` directive map,
+    /// without resolving any cross-directive references. `strict` controls
+    /// whether an unrecognized directive is an error or is collected into
+    /// `Staged::unrecognized`.
+    fn stage(config: &str, strict: bool) -> Result<Staged, ParserError> {
+        let mut staged = Staged::default();
+
+        for (line_no, line) in config.lines().enumerate() {
+            let line_no = line_no + 1;
+            let mut p = Parser::new(line_no, line);
 
-        let directive = tokens[0];
-
-        // Invoke the appropriate parsing function based on the first token
-        match directive {
-            "interface" => self.interfaces.push(InterfaceConfig::try_from(tokens)?),
-            "neighbor" => self.neighbors.push(NeighborConfig::try_from(tokens)?),
-            "routing" => self.parse_routing(&tokens)?,
-            "route" => self.parse_route(&tokens)?,
-            "rip" => self.parse_rip(&tokens)?,
-            _ => {
-                return Err(ParserError::Other(format!(
-                    "Invalid directive: {directive}",
-                )))
+            if p.is_empty() {
+                continue;
             }
+
+            let directive_col = p.col();
+            let directive = p.read_token()?;
+
+            let result = match directive {
+                "interface" => InterfaceConfig::parse(&mut p)
+                    .map(|iface| staged.interfaces.push((line_no, iface)))
+                    .and_then(|()| Self::expect_end_of_line(&mut p)),
+                "neighbor" => NeighborConfig::parse(&mut p)
+                    .map(|neighbor| staged.neighbors.push((line_no, neighbor)))
+                    .and_then(|()| Self::expect_end_of_line(&mut p)),
+                "routing" => RoutingType::try_from(p.read_token()?)
+                    .map(|mode| staged.routing_mode = mode)
+                    .and_then(|()| Self::expect_end_of_line(&mut p)),
+                "route" => Self::stage_route(&mut p)
+                    .map(|route| staged.static_routes.push(route))
+                    .and_then(|()| Self::expect_end_of_line(&mut p)),
+                "rip" => Self::stage_rip(&mut p, line_no, &mut staged)
+                    .and_then(|()| Self::expect_end_of_line(&mut p)),
+                _ => {
+                    if strict {
+                        Err(ParserError::Expected {
+                            line: line_no,
+                            col: directive_col,
+                            expected: String::from(
+                                "a known directive (interface/neighbor/routing/route/rip)",
+                            ),
+                        })
+                    } else {
+                        let args = p
+                            .remaining_tokens()
+                            .into_iter()
+                            .map(String::from)
+                            .collect();
+                        staged.unrecognized.push((String::from(directive), args));
+                        Ok(())
+                    }
+                }
+            };
+
+            result.map_err(|e| ParserError::InvalidLine(String::from(line), Box::new(e)))?;
         }
-        Ok(())
+
+        Ok(staged)
     }
 
-    /// Parse a routing command
-    /// Format: routing <mode>
-    fn parse_routing(&mut self, tokens: &[&str]) -> Result<(), ParserError> {
-        if tokens.len() != 2 {
-            return Err(ParserError::BadFormat);
+    /// Reject trailing garbage left on a line after a directive's required
+    /// tokens have been consumed.
+    fn expect_end_of_line(p: &mut Parser) -> Result<(), ParserError> {
+        if p.is_empty() {
+            Ok(())
+        } else {
+            Err(p.expected("end of line"))
         }
+    }
 
-        self.routing_mode = RoutingType::try_from(tokens[1])?;
-        Ok(())
+    /// Parse the remainder of a `route <prefix> via <addr>` line
+    fn stage_route(p: &mut Parser) -> Result<StaticRoute, ParserError> {
+        let prefix = p.read_ipnet()?;
+        p.expect_keyword("via")?;
+        let addr = p.read_ip()?;
+        Ok((prefix, addr))
     }
 
-    /// Parse a route command
-    /// Format: route <prefix> via <addr>
-    fn parse_route(&mut self, tokens: &[&str]) -> Result<(), ParserError> {
-        if tokens.len() != 4 {
-            return Err(ParserError::BadFormat);
+    /// Stage the remainder of a `rip advertise-to <addr>` or
+    /// `rip originate <prefix>` line; cross-referencing the advertised
+    /// neighbor (or the originated prefix against interfaces) happens later
+    /// in [`IPConfig::assemble`], once every directive has been staged.
+    fn stage_rip(p: &mut Parser, line_no: usize, staged: &mut Staged) -> Result<(), ParserError> {
+        p.skip_ws();
+        let command_col = p.col();
+        let command = p.read_token()?;
+        match command {
+            "advertise-to" => {
+                let addr = p.read_ip()?;
+                staged.rip_advertise.push((line_no, addr));
+                Ok(())
+            }
+            "originate" => {
+                let prefix = p.read_ipnet()?;
+                staged.rip_originate.push((line_no, prefix));
+                Ok(())
+            }
+            _ => Err(ParserError::Expected {
+                line: p.line_no,
+                col: command_col,
+                expected: String::from("keyword `advertise-to` or `originate`"),
+            }),
         }
+    }
 
-        let prefix: Ipv4Net = tokens[1].parse()?;
-        if tokens[2] != "via" {
-            return Err(ParserError::MissingToken(String::from("via")));
-        }
-        let addr: Ipv4Addr = tokens[3].parse()?;
+    /// Second pass: validate cross-directive references against the fully
+    /// staged config and assemble the typed `IPConfig`. Because every
+    /// directive has already been staged, a `rip advertise-to` or a
+    /// `neighbor` may refer to something declared later in the file.
+    fn assemble(staged: Staged) -> Result<Self, ParserError> {
+        let interfaces: Vec<InterfaceConfig> =
+            staged.interfaces.into_iter().map(|(_, iface)| iface).collect();
 
-        self.static_routes.push((prefix, addr));
-        Ok(())
-    }
+        let mut neighbors = Vec::with_capacity(staged.neighbors.len());
+        for (line_no, neighbor) in staged.neighbors {
+            let iface = interfaces
+                .iter()
+                .find(|i| i.name == neighbor.interface_name)
+                .ok_or_else(|| {
+                    Self::line_error(
+                        line_no,
+                        &format!("No interface named {}", neighbor.interface_name),
+                    )
+                })?;
+
+            if !same_family(iface.assigned_ip, neighbor.dest_addr) {
+                return Err(Self::line_error(
+                    line_no,
+                    &format!(
+                        "Neighbor {} is not the same address family as interface {} ({})",
+                        neighbor.dest_addr, iface.name, iface.assigned_ip
+                    ),
+                ));
+            }
 
-    /// Parse a RIP command
-    /// Format: rip advertise-to <addr>
-    fn parse_rip(&mut self, tokens: &[&str]) -> Result<(), ParserError> {
-        // NOTE: originating-prefix (command == "originate") is unsupported as of F23
-        //       so we only need to handle command == "advertise-to"
-        if tokens.len() != 3 {
-            return Err(ParserError::BadFormat);
+            neighbors.push(neighbor);
         }
 
-        let command = tokens[1];
-        if command != "advertise-to" {
-            return Err(ParserError::Other(format!("Invalid command: {command}")));
+        let mut rip_neighbors = None;
+        for (line_no, addr) in staged.rip_advertise {
+            let neighbor = neighbors
+                .iter()
+                .find(|n| n.dest_addr == addr)
+                .ok_or_else(|| Self::line_error(line_no, &format!("No neighbor with address {addr}")))?;
+            rip_neighbors
+                .get_or_insert_with(Vec::new)
+                .push(neighbor.dest_addr);
         }
 
-        let addr: Ipv4Addr = tokens[2].parse()?;
-        let matching_neighbor = self.neighbors.iter().find(|n| n.dest_addr == addr);
-
-        match matching_neighbor {
-            Some(neighbor) => {
-                if let Some(rip_neighbors) = &mut self.rip_neighbors {
-                    rip_neighbors.push(neighbor.dest_addr);
-                } else {
-                    // If rip_neighbors is None, create a new vec with the
-                    // neighbor's address
-                    self.rip_neighbors = Some(vec![neighbor.dest_addr]);
-                }
-            }
-            None => {
-                return Err(ParserError::Other(format!(
-                    "No neighbor with address {addr}"
-                )))
+        let mut rip_originate = Vec::with_capacity(staged.rip_originate.len());
+        for (line_no, prefix) in staged.rip_originate {
+            // Two CIDR prefixes can never partially overlap: either they're
+            // disjoint, or one wholly contains the other. So checking
+            // containment in both directions catches any overlap, not just
+            // an exact match.
+            let colliding_iface = interfaces.iter().find(|iface| {
+                iface.assigned_prefix.contains(&prefix) || prefix.contains(&iface.assigned_prefix)
+            });
+            if let Some(iface) = colliding_iface {
+                return Err(Self::line_error(
+                    line_no,
+                    &format!(
+                        "Originated prefix {prefix} collides with interface {}'s assigned prefix {}",
+                        iface.name, iface.assigned_prefix
+                    ),
+                ));
             }
+            rip_originate.push(prefix);
         }
-        Ok(())
+
+        Ok(Self {
+            interfaces,
+            neighbors,
+            routing_mode: staged.routing_mode,
+            rip_neighbors,
+            rip_originate,
+            static_routes: staged.static_routes,
+            unrecognized: staged.unrecognized,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::net::Ipv4Addr;
+    use std::net::{Ipv4Addr, Ipv6Addr};
 
     #[test]
-    fn test_str_to_udp() {
-        let (ip, port) = str_to_udp("192.168.1.1:8080");
-        assert_eq!(ip, Ipv4Addr::new(192, 168, 1, 1));
-        assert_eq!(port, 8080);
+    fn test_read_token() {
+        let mut p = Parser::new(1, "  interface eth0");
+        assert_eq!(p.read_token().unwrap(), "interface");
+        assert_eq!(p.read_token().unwrap(), "eth0");
+        assert!(p.read_token().is_err());
     }
 
     #[test]
-    fn test_interface_config_try_from() {
-        let tokens = vec!["interface", "eth0", "192.168.1.1/24", "10.0.0.1:9000"];
-        let config = InterfaceConfig::try_from(tokens).unwrap();
+    fn test_read_atomically_rewinds_on_failure() {
+        let mut p = Parser::new(1, "eth0 via");
+        let result: Result<(), ParserError> = p.read_atomically(|p| {
+            p.read_token()?; // consumes "eth0"
+            p.expect_keyword("via")?;
+            Err(p.expected("never matches"))
+        });
+        assert!(result.is_err());
+        // The whole atomic block failed, so nothing should have been consumed.
+        assert_eq!(p.read_token().unwrap(), "eth0");
+    }
+
+    #[test]
+    fn test_read_udp_endpoint_ipv6_brackets() {
+        let mut p = Parser::new(1, "[::1]:9000");
+        let endpoint = p.read_udp_endpoint().unwrap();
+        assert_eq!(
+            endpoint,
+            UdpEndpoint::Addr(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 9000))
+        );
+    }
+
+    #[test]
+    fn test_expected_error_reports_line_and_column() {
+        let mut config = IPConfig::default();
+        let err = config
+            .parse("\n\ninterface eth0 not_an_ip 10.0.0.1:9000\n")
+            .unwrap_err();
+        let ParserError::InvalidLine(_, inner) = err else {
+            panic!("expected ParserError::InvalidLine, got {err:?}");
+        };
+        match *inner {
+            ParserError::Expected { line, col, .. } => {
+                assert_eq!(line, 3);
+                assert_eq!(col, "interface eth0 ".len() + 1);
+            }
+            other => panic!("expected ParserError::Expected, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_expected_error_column_unaffected_by_trailing_comment() {
+        let mut config = IPConfig::default();
+        let err = config
+            .parse("interface eth0 not_an_ip 10.0.0.1:9000  # a trailing comment explaining this line")
+            .unwrap_err();
+        let ParserError::InvalidLine(_, inner) = err else {
+            panic!("expected ParserError::InvalidLine, got {err:?}");
+        };
+        match *inner {
+            ParserError::Expected { col, .. } => {
+                assert_eq!(col, "interface eth0 ".len() + 1);
+            }
+            other => panic!("expected ParserError::Expected, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_interface_config_parse() {
+        let mut p = Parser::new(1, "eth0 192.168.1.1/24 10.0.0.1:9000");
+        let config = InterfaceConfig::parse(&mut p).unwrap();
 
         assert_eq!(config.name, "eth0");
         assert_eq!(config.assigned_prefix.to_string(), "192.168.1.1/24");
-        assert_eq!(config.assigned_ip, Ipv4Addr::new(192, 168, 1, 1));
-        assert_eq!(config.udp_addr, Ipv4Addr::new(10, 0, 0, 1));
-        assert_eq!(config.udp_port, 9000);
+        assert_eq!(config.assigned_ip, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(
+            config.udp_endpoint,
+            UdpEndpoint::Addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 9000))
+        );
     }
 
     #[test]
-    fn test_interface_config_invalid() {
-        let tokens = vec!["interface", "eth0", "invalid_ip", "10.0.0.1:9000"];
-        let result = InterfaceConfig::try_from(tokens);
-        assert!(result.is_err());
+    fn test_interface_config_parse_ipv6() {
+        let mut p = Parser::new(1, "eth0 fd00::1/64 [::1]:9000");
+        let config = InterfaceConfig::parse(&mut p).unwrap();
+
+        assert_eq!(config.assigned_prefix.to_string(), "fd00::1/64");
+        assert_eq!(config.assigned_ip, IpAddr::V6("fd00::1".parse().unwrap()));
+        assert_eq!(
+            config.udp_endpoint,
+            UdpEndpoint::Addr(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 9000))
+        );
     }
 
     #[test]
-    fn test_neighbor_config_try_from() {
-        let tokens = vec![
-            "neighbor",
-            "192.168.1.2",
-            "at",
-            "10.0.0.2:9001",
-            "via",
-            "eth1",
-        ];
-        let config = NeighborConfig::try_from(tokens).unwrap();
+    fn test_interface_config_parse_invalid_ip() {
+        let mut p = Parser::new(1, "eth0 invalid_ip 10.0.0.1:9000");
+        assert!(InterfaceConfig::parse(&mut p).is_err());
+    }
 
-        assert_eq!(config.dest_addr, Ipv4Addr::new(192, 168, 1, 2));
-        assert_eq!(config.udp_addr, Ipv4Addr::new(10, 0, 0, 2));
-        assert_eq!(config.udp_port, 9001);
+    #[test]
+    fn test_neighbor_config_parse() {
+        let mut p = Parser::new(1, "192.168.1.2 at 10.0.0.2:9001 via eth1");
+        let config = NeighborConfig::parse(&mut p).unwrap();
+
+        assert_eq!(config.dest_addr, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2)));
+        assert_eq!(
+            config.udp_endpoint,
+            UdpEndpoint::Addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)), 9001))
+        );
         assert_eq!(config.interface_name, "eth1");
     }
 
     #[test]
-    fn test_neighbor_config_invalid() {
-        let tokens = vec![
-            "neighbor",
-            "192.168.1.2",
-            "at",
-            "10.0.0.2:9001",
-            "invalid",
-            "eth1",
-        ];
-        let result = NeighborConfig::try_from(tokens);
+    fn test_neighbor_config_parse_missing_via() {
+        let mut p = Parser::new(1, "192.168.1.2 at 10.0.0.2:9001 invalid eth1");
+        assert!(NeighborConfig::parse(&mut p).is_err());
+    }
+
+    #[test]
+    fn test_stage_routing() {
+        let staged = IPConfig::stage("routing static", false).unwrap();
+        assert_eq!(staged.routing_mode, RoutingType::Static);
+    }
+
+    #[test]
+    fn test_stage_route() {
+        let staged = IPConfig::stage("route 192.168.1.0/24 via 10.0.0.1", false).unwrap();
+        assert_eq!(staged.static_routes.len(), 1);
+        assert_eq!(staged.static_routes[0].0.to_string(), "192.168.1.0/24");
+        assert_eq!(
+            staged.static_routes[0].1,
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))
+        );
+    }
+
+    #[test]
+    fn test_stage_rip_originate() {
+        let staged = IPConfig::stage("rip originate 10.0.0.0/24", false).unwrap();
+        assert_eq!(staged.rip_originate, vec![(1, "10.0.0.0/24".parse().unwrap())]);
+    }
+
+    #[test]
+    fn test_rip_advertise_to_allows_forward_reference() {
+        let mut config = IPConfig::default();
+        config
+            .parse(
+                "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+                 rip advertise-to 192.168.1.2\n\
+                 neighbor 192.168.1.2 at 10.0.0.2:9001 via eth0\n",
+            )
+            .unwrap();
+
+        assert_eq!(
+            config.rip_neighbors.unwrap(),
+            vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2))]
+        );
+    }
+
+    #[test]
+    fn test_rip_advertise_to_unknown_neighbor_errors() {
+        let mut config = IPConfig::default();
+        let result = config.parse("rip advertise-to 192.168.1.2\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_rip_originate_collides_with_interface() {
+        let mut config = IPConfig::default();
+        let result = config.parse(
+            "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+             rip originate 192.168.1.1/24\n",
+        );
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_parse_routing() {
+    fn test_parse_rip_originate_collides_on_subnet_overlap() {
+        // 192.168.1.0/25 doesn't exactly match the interface's /24, but it's
+        // fully contained within it, so it's still a collision.
         let mut config = IPConfig::default();
-        config.parse_routing(&["routing", "static"]).unwrap();
-        assert_eq!(config.routing_mode, RoutingType::Static);
+        let result = config.parse(
+            "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+             rip originate 192.168.1.0/25\n",
+        );
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_parse_route() {
+    fn test_parse_rip_advertise_to_and_originate_interleaved() {
         let mut config = IPConfig::default();
         config
-            .parse_route(&["route", "192.168.1.0/24", "via", "10.0.0.1"])
+            .parse(
+                "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+                 neighbor 192.168.1.2 at 10.0.0.2:9001 via eth0\n\
+                 rip originate 10.0.0.0/24\n\
+                 rip advertise-to 192.168.1.2\n\
+                 rip originate 10.0.1.0/24\n",
+            )
             .unwrap();
-        assert_eq!(config.static_routes.len(), 1);
-        assert_eq!(config.static_routes[0].0.to_string(), "192.168.1.0/24");
-        assert_eq!(config.static_routes[0].1, Ipv4Addr::new(10, 0, 0, 1));
+
+        assert_eq!(config.rip_neighbors.unwrap().len(), 1);
+        assert_eq!(config.rip_originate.len(), 2);
+    }
+
+    #[test]
+    fn test_stage_skips_comment_lines() {
+        let staged = IPConfig::stage("# This is a comment", false).unwrap();
+        assert!(staged.interfaces.is_empty());
     }
 
     #[test]
-    fn test_parse_rip() {
+    fn test_parse_interface() {
         let mut config = IPConfig::default();
-        config.neighbors.push(NeighborConfig {
-            dest_addr: Ipv4Addr::new(192, 168, 1, 2),
-            udp_addr: Ipv4Addr::new(10, 0, 0, 2),
-            udp_port: 9001,
-            interface_name: String::from("eth1"),
-        });
+        config
+            .parse("interface eth0 192.168.1.1/24 10.0.0.1:9000\n")
+            .unwrap();
+        assert_eq!(config.interfaces.len(), 1);
+        assert_eq!(config.interfaces[0].name, "eth0");
+    }
 
+    #[test]
+    fn test_stage_rejects_trailing_garbage_after_interface() {
+        let result = IPConfig::stage(
+            "interface eth0 192.168.1.1/24 10.0.0.1:9000 garbage_extra_token\n",
+            false,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stage_rejects_trailing_garbage_after_neighbor() {
+        let result = IPConfig::stage(
+            "neighbor 192.168.1.2 at 10.0.0.2:9001 via eth0 extra_garbage\n",
+            false,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stage_rejects_trailing_garbage_after_routing() {
+        let result = IPConfig::stage("routing static extra_garbage\n", false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stage_rejects_trailing_garbage_after_route() {
+        let result = IPConfig::stage("route 10.0.0.0/24 via 192.168.1.1 extra\n", false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stage_rejects_trailing_garbage_after_rip() {
+        let result = IPConfig::stage("rip advertise-to 192.168.1.2 extra_garbage\n", false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_unrecognized_directive_collected_by_default() {
+        let mut config = IPConfig::default();
+        config.parse("bogus eth0 extra\n").unwrap();
+        assert_eq!(
+            config.unrecognized,
+            vec![(
+                String::from("bogus"),
+                vec![String::from("eth0"), String::from("extra")]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_unrecognized_directive_errors_in_strict_mode() {
+        let mut config = IPConfig::default();
+        let result = config.parse_strict("bogus eth0\n");
+        assert!(matches!(result, Err(ParserError::InvalidLine(_, _))));
+    }
+
+    #[test]
+    fn test_parse_mixed_v4_v6_config() {
+        let mut config = IPConfig::default();
         config
-            .parse_rip(&["rip", "advertise-to", "192.168.1.2"])
+            .parse(
+                "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+                 interface eth1 fd00::1/64 [::1]:9001\n\
+                 neighbor 192.168.1.2 at 10.0.0.2:9000 via eth0\n\
+                 neighbor fd00::2 at [::2]:9001 via eth1\n",
+            )
             .unwrap();
-        assert!(config.rip_neighbors.is_some());
+
+        assert_eq!(config.interfaces.len(), 2);
+        assert_eq!(config.neighbors.len(), 2);
         assert_eq!(
-            config.rip_neighbors.unwrap()[0],
-            Ipv4Addr::new(192, 168, 1, 2)
+            config.neighbors[1].dest_addr,
+            IpAddr::V6("fd00::2".parse().unwrap())
         );
     }
 
     #[test]
-    fn test_parse_rip_no_matching_neighbor() {
+    fn test_neighbor_family_mismatch_rejected() {
         let mut config = IPConfig::default();
-        let result = config.parse_rip(&["rip", "advertise-to", "192.168.1.2"]);
+        let result = config.parse(
+            "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+             neighbor fd00::2 at [::2]:9001 via eth0\n",
+        );
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_parse_line_comment() {
+    fn test_neighbor_unknown_interface_rejected() {
         let mut config = IPConfig::default();
-        let result = config.parse_line("# This is a comment");
-        assert!(result.is_ok());
-        assert!(config.interfaces.is_empty());
+        let result = config.parse("neighbor 192.168.1.2 at 10.0.0.2:9001 via eth0\n");
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_parse_line_interface() {
+    fn test_cross_ref_errors_carry_structured_line_number() {
+        let mut config = IPConfig::default();
+        let err = config
+            .parse(
+                "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+                 neighbor fd00::2 at [::2]:9001 via eth0\n",
+            )
+            .unwrap_err();
+        match err {
+            ParserError::CrossRef { line, .. } => assert_eq!(line, 2),
+            other => panic!("expected ParserError::CrossRef, got {other:?}"),
+        }
+    }
+
+    /// Property: for any valid config, parse -> `to_string` -> parse is a no-op.
+    #[test]
+    fn test_roundtrip_serialization_is_idempotent() {
+        let configs = [
+            "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+             neighbor 192.168.1.2 at 10.0.0.2:9001 via eth0\n\
+             routing static\n\
+             route 10.0.0.0/24 via 192.168.1.2\n",
+            "interface eth0 fd00::1/64 [::1]:9000\n\
+             neighbor fd00::2 at [::2]:9001 via eth0\n\
+             routing rip\n\
+             rip advertise-to fd00::2\n",
+            "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+             interface eth1 10.0.1.1/24 10.0.1.1:9001\n\
+             routing none\n",
+            "interface eth0 192.168.1.1/24 10.0.0.1:9000\n\
+             routing none\n\
+             future-directive foo bar\n",
+        ];
+
+        for original in configs {
+            let mut parsed = IPConfig::default();
+            parsed.parse(original).unwrap();
+
+            let rendered = parsed.to_string();
+
+            let mut reparsed = IPConfig::default();
+            reparsed
+                .parse(&rendered)
+                .unwrap_or_else(|e| panic!("failed to re-parse rendered config:\n{rendered}\n{e}"));
+
+            assert_eq!(parsed, reparsed, "roundtrip mismatch for:\n{rendered}");
+        }
+    }
+
+    #[test]
+    fn test_udp_endpoint_parses_literal_addr() {
+        let endpoint: UdpEndpoint = "10.0.0.1:9000".parse().unwrap();
+        assert_eq!(
+            endpoint,
+            UdpEndpoint::Addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 9000))
+        );
+    }
+
+    #[test]
+    fn test_udp_endpoint_parses_hostname() {
+        let endpoint: UdpEndpoint = "node1.cs.brown.edu:9000".parse().unwrap();
+        assert_eq!(
+            endpoint,
+            UdpEndpoint::Hostname(String::from("node1.cs.brown.edu"), 9000)
+        );
+    }
+
+    #[test]
+    fn test_udp_endpoint_rejects_invalid_hostname() {
+        assert!("-bad-label:9000".parse::<UdpEndpoint>().is_err());
+        assert!(format!("{}:9000", "way.too.long.label.".repeat(20))
+            .parse::<UdpEndpoint>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_udp_endpoint_rejects_malformed_ip_literal_as_hostname() {
+        // Out-of-range octets mean this never parses as a SocketAddr; it
+        // must not be silently accepted as a dotted hostname either.
+        assert!("999.999.999.999:9000".parse::<UdpEndpoint>().is_err());
+    }
+
+    #[test]
+    fn test_interface_config_parse_hostname_endpoint() {
+        let mut p = Parser::new(1, "eth0 192.168.1.1/24 node1.local:9000");
+        let config = InterfaceConfig::parse(&mut p).unwrap();
+        assert_eq!(
+            config.udp_endpoint,
+            UdpEndpoint::Hostname(String::from("node1.local"), 9000)
+        );
+    }
+
+    #[test]
+    fn test_resolve_passes_through_literal_addrs() {
         let mut config = IPConfig::default();
         config
-            .parse_line("interface eth0 192.168.1.1/24 10.0.0.1:9000")
+            .parse("interface eth0 192.168.1.1/24 10.0.0.1:9000\n")
             .unwrap();
-        assert_eq!(config.interfaces.len(), 1);
-        assert_eq!(config.interfaces[0].name, "eth0");
+
+        let resolved = config.resolve().unwrap();
+        assert_eq!(resolved.interfaces[0].udp_endpoint, config.interfaces[0].udp_endpoint);
+    }
+
+    #[test]
+    fn test_resolve_surfaces_lookup_failure() {
+        let mut config = IPConfig::default();
+        config
+            .parse("interface eth0 192.168.1.1/24 this-host-does-not-exist.invalid:9000\n")
+            .unwrap();
+
+        let result = config.resolve();
+        assert!(matches!(result, Err(ParserError::Resolve(_))));
     }
 }